@@ -0,0 +1,296 @@
+//! HNSW (Hierarchical Navigable Small World) approximate nearest-neighbor
+//! index over sonar-record feature vectors, for loop-closure / mosaic
+//! alignment queries ("find the K most similar pings to this one").
+
+use rand::Rng;
+use rayon::prelude::*;
+use std::collections::BinaryHeap;
+
+const DEFAULT_M: usize = 16;
+const DEFAULT_EF_CONSTRUCTION: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ScoredNode {
+    dist: f32,
+    node: u32,
+}
+
+impl Eq for ScoredNode {}
+impl Ord for ScoredNode {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.dist.partial_cmp(&other.dist).unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+impl PartialOrd for ScoredNode {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A multi-layer proximity graph over feature vectors. Each node keeps up
+/// to `m` bidirectional neighbors per layer (`2*m` on layer 0).
+pub struct HnswIndex {
+    vectors: Vec<Vec<f32>>,
+    layers: Vec<Vec<Vec<u32>>>,
+    node_top_level: Vec<usize>,
+    entry_point: u32,
+    m: usize,
+    ef_construction: usize,
+    level_norm: f32,
+}
+
+impl HnswIndex {
+    /// Builds an index over `vectors`. Feature-vector extraction upstream is
+    /// parallelized via rayon; the max-level assignment per node is
+    /// independent and is also computed in parallel before the (inherently
+    /// sequential) graph-linking insertion pass.
+    pub fn build(vectors: Vec<Vec<f32>>, m: usize, ef_construction: usize) -> Self {
+        let m = m.max(1);
+        let level_norm = 1.0 / (m as f32).ln();
+
+        let node_levels: Vec<usize> = (0..vectors.len())
+            .into_par_iter()
+            .map(|_| {
+                let mut rng = rand::thread_rng();
+                let u: f32 = rng.gen_range(1e-9..1.0);
+                (-u.ln() * level_norm).floor() as usize
+            })
+            .collect();
+
+        let max_level = node_levels.iter().copied().max().unwrap_or(0);
+        let mut index = HnswIndex {
+            vectors,
+            layers: vec![Vec::new(); max_level + 1],
+            node_top_level: node_levels,
+            entry_point: 0,
+            m,
+            ef_construction: ef_construction.max(m),
+            level_norm,
+        };
+
+        for layer in index.layers.iter_mut() {
+            layer.resize(index.vectors.len(), Vec::new());
+        }
+
+        if index.vectors.is_empty() {
+            return index;
+        }
+
+        index.entry_point = 0;
+        for node in 1..index.vectors.len() {
+            index.insert(node as u32);
+        }
+
+        index
+    }
+
+    fn insert(&mut self, node: u32) {
+        let top_level = self.node_top_level[node as usize];
+        let mut current = self.entry_point;
+        let entry_level = self.node_top_level[current as usize];
+
+        // Greedily descend from the entry point through layers above this
+        // node's top level, always moving to the closest neighbor.
+        for level in (top_level + 1..=entry_level).rev() {
+            current = self.greedy_closest(node, current, level);
+        }
+
+        for level in (0..=top_level.min(entry_level)).rev() {
+            let candidates = self.search_layer(node, current, self.ef_construction, level);
+            let selected = self.select_neighbors(node, &candidates, self.neighbor_cap(level));
+
+            self.layers[level][node as usize] = selected.clone();
+            for &neighbor in &selected {
+                self.link(neighbor, node, level);
+            }
+
+            if let Some(&closest) = selected.first() {
+                current = closest;
+            }
+        }
+
+        if top_level > entry_level {
+            self.entry_point = node;
+        }
+    }
+
+    fn neighbor_cap(&self, level: usize) -> usize {
+        if level == 0 {
+            self.m * 2
+        } else {
+            self.m
+        }
+    }
+
+    /// Links `node` as a neighbor of `target` at `level`, pruning `target`'s
+    /// neighbor list back down to its degree cap via `select_neighbors` if
+    /// it overflows.
+    fn link(&mut self, target: u32, node: u32, level: usize) {
+        let cap = self.neighbor_cap(level);
+        let list = &mut self.layers[level][target as usize];
+        if !list.contains(&node) {
+            list.push(node);
+        }
+
+        if list.len() > cap {
+            let candidates: Vec<u32> = list.clone();
+            let pruned = self.select_neighbors(target, &candidates, cap);
+            self.layers[level][target as usize] = pruned;
+        }
+    }
+
+    fn greedy_closest(&self, query_node: u32, start: u32, level: usize) -> u32 {
+        let mut current = start;
+        let mut current_dist = self.distance_nodes(query_node, current);
+        loop {
+            let mut improved = false;
+            for &neighbor in &self.layers[level][current as usize] {
+                let dist = self.distance_nodes(query_node, neighbor);
+                if dist < current_dist {
+                    current_dist = dist;
+                    current = neighbor;
+                    improved = true;
+                }
+            }
+            if !improved {
+                return current;
+            }
+        }
+    }
+
+    /// Beam search with candidate set size `ef` at a given layer, returning
+    /// the visited candidates sorted by distance (closest first).
+    fn search_layer(&self, query_node: u32, start: u32, ef: usize, level: usize) -> Vec<u32> {
+        self.search_layer_vec(&self.vectors[query_node as usize].clone(), start, ef, level)
+    }
+
+    fn search_layer_vec(&self, query: &[f32], start: u32, ef: usize, level: usize) -> Vec<u32> {
+        let mut visited = std::collections::HashSet::new();
+        visited.insert(start);
+
+        let start_dist = self.distance(query, &self.vectors[start as usize]);
+        let mut candidates: BinaryHeap<std::cmp::Reverse<ScoredNode>> = BinaryHeap::new();
+        candidates.push(std::cmp::Reverse(ScoredNode { dist: start_dist, node: start }));
+
+        let mut result: BinaryHeap<ScoredNode> = BinaryHeap::new();
+        result.push(ScoredNode { dist: start_dist, node: start });
+
+        while let Some(std::cmp::Reverse(current)) = candidates.pop() {
+            let worst_result = result.peek().map(|s| s.dist).unwrap_or(f32::MAX);
+            if current.dist > worst_result && result.len() >= ef {
+                break;
+            }
+
+            for &neighbor in &self.layers[level][current.node as usize] {
+                if visited.contains(&neighbor) {
+                    continue;
+                }
+                visited.insert(neighbor);
+
+                let dist = self.distance(query, &self.vectors[neighbor as usize]);
+                let worst_result = result.peek().map(|s| s.dist).unwrap_or(f32::MAX);
+                if result.len() < ef || dist < worst_result {
+                    candidates.push(std::cmp::Reverse(ScoredNode { dist, node: neighbor }));
+                    result.push(ScoredNode { dist, node: neighbor });
+                    if result.len() > ef {
+                        result.pop();
+                    }
+                }
+            }
+        }
+
+        let mut out: Vec<ScoredNode> = result.into_vec();
+        out.sort_by(|a, b| a.dist.total_cmp(&b.dist));
+        out.into_iter().map(|s| s.node).collect()
+    }
+
+    /// "Keep closest" neighbor heuristic: sorts candidates by distance to
+    /// `node` and keeps the nearest `cap`.
+    fn select_neighbors(&self, node: u32, candidates: &[u32], cap: usize) -> Vec<u32> {
+        let mut scored: Vec<ScoredNode> = candidates
+            .iter()
+            .filter(|&&c| c != node)
+            .map(|&c| ScoredNode { dist: self.distance_nodes(node, c), node: c })
+            .collect();
+        scored.sort_by(|a, b| a.dist.total_cmp(&b.dist));
+        scored.truncate(cap);
+        scored.into_iter().map(|s| s.node).collect()
+    }
+
+    fn distance_nodes(&self, a: u32, b: u32) -> f32 {
+        self.distance(&self.vectors[a as usize], &self.vectors[b as usize])
+    }
+
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        a.iter()
+            .zip(b.iter())
+            .map(|(&x, &y)| (x - y) * (x - y))
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    /// Descends greedily to layer 0 from the entry point, then beam-searches
+    /// with candidate set size `ef`, returning the `k` closest node indices.
+    pub fn query(&self, query: &[f32], k: usize, ef: usize) -> Vec<usize> {
+        if self.vectors.is_empty() {
+            return Vec::new();
+        }
+
+        let mut current = self.entry_point;
+        let top_level = self.node_top_level[self.entry_point as usize];
+        for level in (1..=top_level).rev() {
+            current = self.greedy_closest_vec(query, current, level);
+        }
+
+        let candidates = self.search_layer_vec(query, current, ef.max(k), 0);
+        candidates.into_iter().take(k).map(|n| n as usize).collect()
+    }
+
+    fn greedy_closest_vec(&self, query: &[f32], start: u32, level: usize) -> u32 {
+        let mut current = start;
+        let mut current_dist = self.distance(query, &self.vectors[current as usize]);
+        loop {
+            let mut improved = false;
+            for &neighbor in &self.layers[level][current as usize] {
+                let dist = self.distance(query, &self.vectors[neighbor as usize]);
+                if dist < current_dist {
+                    current_dist = dist;
+                    current = neighbor;
+                    improved = true;
+                }
+            }
+            if !improved {
+                return current;
+            }
+        }
+    }
+}
+
+pub fn default_m() -> usize {
+    DEFAULT_M
+}
+
+pub fn default_ef_construction() -> usize {
+    DEFAULT_EF_CONSTRUCTION
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_returns_nearest_vectors() {
+        let vectors = vec![
+            vec![0.0, 0.0],
+            vec![0.1, 0.0],
+            vec![10.0, 10.0],
+            vec![10.1, 10.0],
+            vec![5.0, 5.0],
+        ];
+        let index = HnswIndex::build(vectors, 8, 50);
+
+        let results = index.query(&[0.0, 0.0], 2, 50);
+        assert!(results.contains(&0));
+        assert!(results.contains(&1));
+    }
+}
@@ -0,0 +1,113 @@
+//! Track kinematics derived from consecutive GPS fixes: speed-over-ground,
+//! course-over-ground, and turn rate. Along-track speed drives slant-range
+//! aspect-ratio correction, and turn rate flags pings taken during sharp
+//! turns where sidescan imagery is distorted.
+
+use rayon::prelude::*;
+
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Computes `(speed_mps, course_deg, turn_rate_deg_per_s)` parallel arrays,
+/// one entry per input fix. The first fix has no predecessor, so it carries
+/// the following fix's course and zero speed/turn rate.
+pub fn compute_track_kinematics(lats: &[f64], lons: &[f64], timestamps_ms: &[u64]) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let n = lats.len();
+    if n == 0 || lons.len() != n || timestamps_ms.len() != n {
+        return (Vec::new(), Vec::new(), Vec::new());
+    }
+
+    // Per-step distance/bearing/speed bridging fix i-1 -> i, indexed 0..n-1.
+    let bearings: Vec<f64> = (1..n)
+        .into_par_iter()
+        .map(|i| initial_bearing(lats[i - 1], lons[i - 1], lats[i], lons[i]))
+        .collect();
+
+    let speeds: Vec<f64> = (1..n)
+        .into_par_iter()
+        .map(|i| {
+            let distance = great_circle_distance(lats[i - 1], lons[i - 1], lats[i], lons[i]);
+            let dt = dt_seconds(timestamps_ms[i - 1], timestamps_ms[i]);
+            if dt > 0.0 {
+                distance / dt
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    let mut speed_over_ground = vec![0.0; n];
+    let mut course_over_ground = vec![0.0; n];
+    let mut turn_rate = vec![0.0; n];
+
+    for i in 1..n {
+        speed_over_ground[i] = speeds[i - 1];
+        course_over_ground[i] = bearings[i - 1];
+    }
+    if n > 1 {
+        course_over_ground[0] = course_over_ground[1];
+    }
+
+    for i in 2..n {
+        let dt = dt_seconds(timestamps_ms[i - 1], timestamps_ms[i]);
+        let delta = normalize_angle(bearings[i - 1] - bearings[i - 2]);
+        turn_rate[i] = if dt > 0.0 { delta / dt } else { 0.0 };
+    }
+
+    (speed_over_ground, course_over_ground, turn_rate)
+}
+
+/// Seconds between two fix timestamps, as a signed difference so a fix out
+/// of order (truncated/bit-rotted RSD input, which is not guaranteed
+/// monotonic) yields a negative `dt` instead of underflowing the `u64`
+/// subtraction; callers already treat non-positive `dt` as unknown.
+fn dt_seconds(from_ms: u64, to_ms: u64) -> f64 {
+    (to_ms as i64 - from_ms as i64) as f64 / 1000.0
+}
+
+fn great_circle_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let dphi = (lat2 - lat1).to_radians();
+    let dlambda = (lon2 - lon1).to_radians();
+    let a = (dphi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (dlambda / 2.0).sin().powi(2);
+    let c = 2.0 * a.sqrt().asin();
+    EARTH_RADIUS_M * c
+}
+
+fn initial_bearing(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let dlambda = (lon2 - lon1).to_radians();
+    let y = dlambda.sin() * phi2.cos();
+    let x = phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * dlambda.cos();
+    (y.atan2(x).to_degrees() + 360.0) % 360.0
+}
+
+/// Normalizes a bearing delta (degrees) to the range [-180, 180].
+fn normalize_angle(delta_deg: f64) -> f64 {
+    let mut d = delta_deg % 360.0;
+    if d > 180.0 {
+        d -= 360.0;
+    }
+    if d < -180.0 {
+        d += 360.0;
+    }
+    d
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kinematics_of_due_north_track() {
+        // Three fixes, ~111m apart (1/1000 degree), 10 seconds apart.
+        let lats = vec![0.0, 0.001, 0.002];
+        let lons = vec![0.0, 0.0, 0.0];
+        let timestamps_ms = vec![0, 10_000, 20_000];
+
+        let (speed, course, turn_rate) = compute_track_kinematics(&lats, &lons, &timestamps_ms);
+
+        assert!((speed[1] - 11.1).abs() < 1.0);
+        assert!(course[1].abs() < 1.0);
+        assert!(turn_rate[2].abs() < 1.0);
+    }
+}
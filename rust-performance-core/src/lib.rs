@@ -18,12 +18,22 @@ use std::io::{Result, Seek, SeekFrom};
 #[cfg(target_arch = "x86_64")]
 use wide::f32x8;
 
+mod reader;
+use reader::{FromReader, Reader, ToWriter};
+
+mod ann;
+mod kinematics;
+mod checksum;
+use checksum::{verify_record_crc, IntegrityRange, IntegrityReport};
+mod columnar;
+
 /// High-performance binary data parser
 #[pyclass]
 pub struct FastBinaryParser {
     mmap: Arc<Mmap>,
     cache: Arc<RwLock<AHashMap<u64, SmallVec<[u8; 64]>>>>,
     file_size: usize,
+    ann_index: RwLock<Option<ann::HnswIndex>>,
 }
 
 /// Sonar record structure optimized for SIMD processing
@@ -50,6 +60,79 @@ struct SonarRecord {
     reserved: u16,
 }
 
+/// Decodes a `SonarRecord` off a bounded cursor using the real on-disk byte
+/// ranges of the hand-rolled offset parser this replaced — it is not a
+/// purely sequential field walk. In particular: the leading 8 bytes are an
+/// unidentified/reserved header word (the caller overwrites `offset` with
+/// the file position afterward — see `try_parse_binary_record`);
+/// `sample_count` lives in the trailing 4 bytes of `latitude`'s on-disk
+/// range rather than its own slot; and 4 bytes between `depth_m` and
+/// `sonar_offset` are unused padding, skipped rather than stored.
+impl FromReader for SonarRecord {
+    fn from_reader(r: &mut Reader) -> Option<Self> {
+        let offset: u64 = r.read()?;
+        let channel_id: u32 = r.read()?;
+        let sequence: u32 = r.read()?;
+        let timestamp_ms: u64 = r.read()?;
+        let latitude: f64 = r.read()?;
+        let longitude: f64 = r.read()?;
+        let depth_m: f32 = r.read()?;
+
+        let sample_count = u32::from_le_bytes(r.peek_at(28, 4)?.try_into().ok()?);
+        let _padding = r.take(4)?;
+
+        Some(SonarRecord {
+            offset,
+            channel_id,
+            sequence,
+            timestamp_ms,
+            latitude,
+            longitude,
+            depth_m,
+            sample_count,
+            sonar_offset: r.read()?,
+            sonar_size: r.read()?,
+            beam_angle: r.read()?,
+            pitch: r.read()?,
+            roll: r.read()?,
+            heave: r.read()?,
+            tx_offset: r.read()?,
+            rx_offset: r.read()?,
+            color_id: r.read()?,
+            reserved: 0,
+        })
+    }
+}
+
+/// Re-encodes a `SonarRecord` as a plain sequential field walk. This is
+/// not a byte-exact inverse of `FromReader` — the on-disk format it reads
+/// packs `sample_count` into `latitude`'s trailing bytes and has unused
+/// padding, neither of which a from-scratch write can faithfully
+/// reproduce — but nothing currently writes `SonarRecord`s back to a real
+/// Garmin file, so this exists only to mirror `FromReader`'s field order.
+impl ToWriter for SonarRecord {
+    fn to_writer(&self, out: &mut Vec<u8>) {
+        self.offset.to_writer(out);
+        self.channel_id.to_writer(out);
+        self.sequence.to_writer(out);
+        self.timestamp_ms.to_writer(out);
+        self.latitude.to_writer(out);
+        self.longitude.to_writer(out);
+        self.depth_m.to_writer(out);
+        self.sample_count.to_writer(out);
+        self.sonar_offset.to_writer(out);
+        self.sonar_size.to_writer(out);
+        self.beam_angle.to_writer(out);
+        self.pitch.to_writer(out);
+        self.roll.to_writer(out);
+        self.heave.to_writer(out);
+        self.tx_offset.to_writer(out);
+        self.rx_offset.to_writer(out);
+        self.color_id.to_writer(out);
+        self.reserved.to_writer(out);
+    }
+}
+
 #[pymethods]
 impl FastBinaryParser {
     #[new]
@@ -66,6 +149,7 @@ impl FastBinaryParser {
             mmap: Arc::new(mmap),
             cache: Arc::new(RwLock::new(AHashMap::new())),
             file_size,
+            ann_index: RwLock::new(None),
         })
     }
     
@@ -100,7 +184,7 @@ impl FastBinaryParser {
             .map(|records| {
                 let py_list = PyList::empty(py);
                 for record in records {
-                    let py_record = record_to_python(py, &record)?;
+                    let py_record = record_to_python(py, &record, None)?;
                     py_list.append(py_record)?;
                 }
                 Ok(py_list.into())
@@ -162,6 +246,109 @@ impl FastBinaryParser {
         })
     }
     
+    /// Reads a record's variable-length sonar payload, bounded to its
+    /// declared `sonar_size` so a corrupt record can't read past its
+    /// boundary into neighboring data.
+    fn read_sonar_payload(&self, sonar_offset: u64, sonar_size: u32) -> PyResult<PyObject> {
+        let start = sonar_offset as usize;
+        let end = start.saturating_add(sonar_size as usize);
+        if end > self.file_size {
+            return Err(PyErr::new::<pyo3::exceptions::PyIndexError, _>(
+                "sonar payload exceeds file bounds",
+            ));
+        }
+
+        let mut reader = Reader::new(&self.mmap[start..end]);
+        let payload = reader
+            .take(sonar_size as usize)
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyIndexError, _>("sonar payload exceeds record bounds"))?;
+
+        Python::with_gil(|py| Ok(PyBytes::new(py, payload.rest()).into()))
+    }
+
+    /// Builds an HNSW index over records for nearest-neighbor queries (e.g.
+    /// detecting repeat passes over the same seabed during mosaicking).
+    /// Each record is `(latitude, longitude, depth_m, beam_angle)`;
+    /// position is projected into local ENU meters relative to the first
+    /// record before distances are computed. Feature-vector construction is
+    /// parallelized via rayon; graph linking itself is sequential.
+    fn build_ann_index(
+        &self,
+        records: Vec<(f64, f64, f32, f32)>,
+        m: Option<usize>,
+        ef_construction: Option<usize>,
+    ) -> PyResult<()> {
+        if records.is_empty() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "cannot build an index over zero records",
+            ));
+        }
+
+        let (lat0, lon0) = (records[0].0, records[0].1);
+        let vectors: Vec<Vec<f32>> = records
+            .par_iter()
+            .map(|&(lat, lon, depth_m, beam_angle)| {
+                let x = ((lon - lon0) * 111_320.0 * lat0.to_radians().cos()) as f32;
+                let y = ((lat - lat0) * 111_320.0) as f32;
+                vec![x, y, depth_m, beam_angle]
+            })
+            .collect();
+
+        let index = ann::HnswIndex::build(
+            vectors,
+            m.unwrap_or_else(ann::default_m),
+            ef_construction.unwrap_or_else(ann::default_ef_construction),
+        );
+        *self.ann_index.write() = Some(index);
+        Ok(())
+    }
+
+    /// Queries the `k` most similar records to `vector`, beam-searching with
+    /// candidate set size `ef`. Returns the matched record indices.
+    /// `vector` is compared as-is against the indexed feature vectors, so it
+    /// must already be `[x_m, y_m, depth_m, beam_angle]` in the local ENU
+    /// frame `build_ann_index` projects into; no lat/lon projection is done
+    /// here.
+    fn query_knn(&self, vector: Vec<f32>, k: usize, ef: Option<usize>) -> PyResult<Vec<usize>> {
+        let guard = self.ann_index.read();
+        match guard.as_ref() {
+            Some(index) => Ok(index.query(&vector, k, ef.unwrap_or(k.max(50)))),
+            None => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "ANN index not built; call build_ann_index first",
+            )),
+        }
+    }
+
+    /// Walks every candidate record, verifying a trailing CRC32 against the
+    /// recomputed checksum over the record's bytes, and reports which byte
+    /// ranges passed and which didn't so callers can quarantine or skip bad
+    /// ranges rather than emit bogus pings from a truncated/bit-rotted tail.
+    ///
+    /// The Garmin on-disk checksum scheme isn't confirmed yet, so this
+    /// checks a standard CRC32 immediately following each candidate
+    /// record's fixed fields as a placeholder convention.
+    fn scan_integrity(&self, chunk_size: Option<usize>) -> PyResult<(Vec<(usize, usize)>, Vec<(usize, usize)>)> {
+        let record_size = std::mem::size_of::<SonarRecord>();
+        let chunk_size = chunk_size.unwrap_or(4 * 1024 * 1024).max(record_size * 4);
+
+        let chunk_starts: Vec<usize> = (0..self.file_size).step_by(chunk_size).collect();
+        let report = chunk_starts
+            .into_par_iter()
+            .map(|start| {
+                let own_end = (start + chunk_size).min(self.file_size);
+                // Scan an extra record_size+4 bytes past the chunk's own
+                // boundary (when available) so a record starting in the
+                // last record_size+4 bytes of the chunk is still attempted;
+                // `own_len` keeps that overlap from being double-counted by
+                // the next chunk.
+                let scan_end = (own_end + record_size + 4).min(self.file_size);
+                scan_chunk_integrity(&self.mmap[start..scan_end], start, record_size, own_end - start)
+            })
+            .reduce(IntegrityReport::default, IntegrityReport::merge);
+
+        Ok((report.good, report.bad))
+    }
+
     /// Get performance statistics
     fn get_performance_stats(&self) -> PyResult<PyObject> {
         let cache = self.cache.read();
@@ -273,49 +460,50 @@ fn try_parse_binary_record(data: &[u8], file_offset: usize) -> Option<SonarRecor
     if data.len() < std::mem::size_of::<SonarRecord>() {
         return None;
     }
-    
-    // Basic sanity checks for binary data
-    let potential_channel = u32::from_le_bytes([data[8], data[9], data[10], data[11]]);
-    let potential_samples = u32::from_le_bytes([data[28], data[29], data[30], data[31]]);
-    
+
+    let mut reader = Reader::new(data);
+    let mut record: SonarRecord = reader.read()?;
+    record.offset = file_offset as u64;
+
     // Validate ranges
-    if potential_channel > 16 || potential_samples > 100_000 {
+    if record.channel_id > 16 || record.sample_count > 100_000 {
         return None;
     }
-    
-    // Parse full record
-    Some(SonarRecord {
-        offset: file_offset as u64,
-        channel_id: potential_channel,
-        sequence: u32::from_le_bytes([data[12], data[13], data[14], data[15]]),
-        timestamp_ms: u64::from_le_bytes([
-            data[16], data[17], data[18], data[19],
-            data[20], data[21], data[22], data[23],
-        ]),
-        latitude: f64::from_le_bytes([
-            data[24], data[25], data[26], data[27],
-            data[28], data[29], data[30], data[31],
-        ]),
-        longitude: f64::from_le_bytes([
-            data[32], data[33], data[34], data[35],
-            data[36], data[37], data[38], data[39],
-        ]),
-        depth_m: f32::from_le_bytes([data[40], data[41], data[42], data[43]]),
-        sample_count: potential_samples,
-        sonar_offset: u64::from_le_bytes([
-            data[48], data[49], data[50], data[51],
-            data[52], data[53], data[54], data[55],
-        ]),
-        sonar_size: u32::from_le_bytes([data[56], data[57], data[58], data[59]]),
-        beam_angle: f32::from_le_bytes([data[60], data[61], data[62], data[63]]),
-        pitch: f32::from_le_bytes([data[64], data[65], data[66], data[67]]),
-        roll: f32::from_le_bytes([data[68], data[69], data[70], data[71]]),
-        heave: f32::from_le_bytes([data[72], data[73], data[74], data[75]]),
-        tx_offset: f32::from_le_bytes([data[76], data[77], data[78], data[79]]),
-        rx_offset: f32::from_le_bytes([data[80], data[81], data[82], data[83]]),
-        color_id: u16::from_le_bytes([data[84], data[85]]),
-        reserved: 0,
-    })
+
+    Some(record)
+}
+
+/// Scans one chunk for candidate records and verifies each one's trailing
+/// CRC32, accumulating byte ranges into an `IntegrityReport`.
+/// Scans `data` for candidate records, owning only the starts in
+/// `0..own_len` (the chunk's own byte range before any look-ahead overlap)
+/// so a record straddling a chunk boundary is scanned exactly once — by
+/// the chunk it starts in — instead of falling into a gap between chunks.
+fn scan_chunk_integrity(data: &[u8], base_offset: usize, record_size: usize, own_len: usize) -> IntegrityReport {
+    let mut report = IntegrityReport::default();
+    let mut offset = 0;
+
+    while offset < own_len && offset + record_size + 4 <= data.len() {
+        if offset % 4 == 0 {
+            if try_parse_binary_record(&data[offset..], base_offset + offset).is_some() {
+                let record_bytes = &data[offset..offset + record_size];
+                let trailer = &data[offset + record_size..offset + record_size + 4];
+                let stored_crc = u32::from_le_bytes(trailer.try_into().unwrap());
+                let ok = verify_record_crc(record_bytes, stored_crc);
+
+                report.push(IntegrityRange {
+                    start: base_offset + offset,
+                    end: base_offset + offset + record_size,
+                    ok,
+                });
+                offset += record_size;
+                continue;
+            }
+        }
+        offset += 1;
+    }
+
+    report
 }
 
 /// SIMD coordinate transformation
@@ -351,10 +539,13 @@ fn transform_coordinates_vectorized(
     (new_lats, new_lons)
 }
 
-/// Convert Rust record to Python dictionary
-fn record_to_python(py: Python, record: &SonarRecord) -> PyResult<PyObject> {
+/// Convert Rust record to Python dictionary. `kinematics`, when present, is
+/// `(speed_mps, course_deg, turn_rate_deg_per_s)` from
+/// `kinematics::compute_track_kinematics` for this record's position in the
+/// time-ordered stream.
+fn record_to_python(py: Python, record: &SonarRecord, kinematics: Option<(f64, f64, f64)>) -> PyResult<PyObject> {
     let dict = pyo3::types::PyDict::new(py);
-    
+
     dict.set_item("ofs", record.offset)?;
     dict.set_item("channel_id", record.channel_id)?;
     dict.set_item("seq", record.sequence)?;
@@ -372,7 +563,13 @@ fn record_to_python(py: Python, record: &SonarRecord) -> PyResult<PyObject> {
     dict.set_item("tx_ofs_m", record.tx_offset)?;
     dict.set_item("rx_ofs_m", record.rx_offset)?;
     dict.set_item("color_id", record.color_id)?;
-    
+
+    if let Some((speed_mps, course_deg, turn_rate_dps)) = kinematics {
+        dict.set_item("speed_mps", speed_mps)?;
+        dict.set_item("course_deg", course_deg)?;
+        dict.set_item("turn_rate_dps", turn_rate_dps)?;
+    }
+
     Ok(dict.into())
 }
 
@@ -438,13 +635,134 @@ impl FastCSVWriter {
     }
 }
 
+/// Columnar binary writer as a compact alternative to `FastCSVWriter`,
+/// delta+varint encoding monotonic/low-entropy columns so multi-gigabyte
+/// surveys don't blow up into verbose text.
+#[pyclass]
+pub struct FastColumnarWriter {
+    inner: columnar::FastColumnarWriter,
+}
+
+#[pymethods]
+impl FastColumnarWriter {
+    #[new]
+    fn new() -> Self {
+        FastColumnarWriter {
+            inner: columnar::FastColumnarWriter::new(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn append_record(
+        &mut self,
+        offset: u64,
+        channel_id: u32,
+        sequence: u32,
+        timestamp_ms: u64,
+        latitude: f64,
+        longitude: f64,
+        depth_m: f32,
+        sample_count: u32,
+        sonar_offset: u64,
+        sonar_size: u32,
+        beam_angle: f32,
+    ) {
+        self.inner.append_record(&columnar::ColumnarRecord {
+            offset,
+            channel_id,
+            sequence,
+            timestamp_ms,
+            latitude,
+            longitude,
+            depth_m,
+            sample_count,
+            sonar_offset,
+            sonar_size,
+            beam_angle,
+        });
+    }
+
+    fn finish(&self) -> PyResult<PyObject> {
+        let buffer = self.inner.finish();
+        Python::with_gil(|py| Ok(PyBytes::new(py, &buffer).into()))
+    }
+}
+
+/// Reconstructs columnar records (as Python dicts) from a buffer produced
+/// by `FastColumnarWriter::finish`. Decodes every column eagerly; the
+/// per-column length prefixes leave room for a selective geometry-only
+/// reader later, but nothing takes advantage of that yet.
+#[pyfunction]
+fn read_columnar_records(py: Python, data: Vec<u8>) -> PyResult<Vec<PyObject>> {
+    let records = columnar::read_columnar(&data)
+        .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyValueError, _>("malformed columnar buffer"))?;
+
+    records
+        .into_iter()
+        .map(|record| {
+            let dict = pyo3::types::PyDict::new(py);
+            dict.set_item("ofs", record.offset)?;
+            dict.set_item("channel_id", record.channel_id)?;
+            dict.set_item("seq", record.sequence)?;
+            dict.set_item("time_ms", record.timestamp_ms)?;
+            dict.set_item("lat", record.latitude)?;
+            dict.set_item("lon", record.longitude)?;
+            dict.set_item("depth_m", record.depth_m)?;
+            dict.set_item("sample_cnt", record.sample_count)?;
+            dict.set_item("sonar_ofs", record.sonar_offset)?;
+            dict.set_item("sonar_size", record.sonar_size)?;
+            dict.set_item("beam_deg", record.beam_angle)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// Computes speed-over-ground, course-over-ground, and turn rate for a
+/// time-ordered stream of GPS fixes.
+#[pyfunction]
+fn compute_track_kinematics(lats: Vec<f64>, lons: Vec<f64>, timestamps_ms: Vec<u64>) -> PyResult<(Vec<f64>, Vec<f64>, Vec<f64>)> {
+    Ok(kinematics::compute_track_kinematics(&lats, &lons, &timestamps_ms))
+}
+
+/// Attaches track kinematics to an already-parsed stream of Python record
+/// dicts (as produced by `record_to_python`), using each dict's own
+/// `lat`/`lon`/`time_ms` fields as the time-ordered fix sequence.
+#[pyfunction]
+fn attach_track_kinematics(py: Python, records: Vec<PyObject>) -> PyResult<Vec<PyObject>> {
+    let mut lats = Vec::with_capacity(records.len());
+    let mut lons = Vec::with_capacity(records.len());
+    let mut timestamps_ms = Vec::with_capacity(records.len());
+
+    for record in &records {
+        let dict = record.downcast::<pyo3::types::PyDict>(py)?;
+        lats.push(dict.get_item("lat")?.unwrap().extract::<f64>()?);
+        lons.push(dict.get_item("lon")?.unwrap().extract::<f64>()?);
+        timestamps_ms.push(dict.get_item("time_ms")?.unwrap().extract::<u64>()?);
+    }
+
+    let (speed, course, turn_rate) = kinematics::compute_track_kinematics(&lats, &lons, &timestamps_ms);
+
+    for (i, record) in records.iter().enumerate() {
+        let dict = record.downcast::<pyo3::types::PyDict>(py)?;
+        dict.set_item("speed_mps", speed[i])?;
+        dict.set_item("course_deg", course[i])?;
+        dict.set_item("turn_rate_dps", turn_rate[i])?;
+    }
+
+    Ok(records)
+}
+
 /// Python module initialization
 #[pymodule]
 fn rsd_performance_core(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<FastBinaryParser>()?;
     m.add_class::<FastCSVWriter>()?;
+    m.add_class::<FastColumnarWriter>()?;
     
     m.add_function(wrap_pyfunction!(benchmark_simd_search, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_track_kinematics, m)?)?;
+    m.add_function(wrap_pyfunction!(attach_track_kinematics, m)?)?;
+    m.add_function(wrap_pyfunction!(read_columnar_records, m)?)?;
     
     Ok(())
 }
@@ -460,4 +778,44 @@ fn benchmark_simd_search(data: Vec<u8>, pattern: Vec<u8>, iterations: usize) ->
     
     let elapsed = start.elapsed();
     Ok(elapsed.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Locks in `SonarRecord::from_reader`'s on-disk byte mapping against a
+    /// hand-built fixture, so a future refactor can't silently slide
+    /// `sample_count` into the wrong bytes the way this one briefly did.
+    #[test]
+    fn test_try_parse_binary_record_matches_known_byte_layout() {
+        let mut data = vec![0u8; 88];
+        data[0..8].copy_from_slice(&0xAAAA_AAAA_AAAA_AAAAu64.to_le_bytes()); // unidentified header, ignored
+        data[8..12].copy_from_slice(&7u32.to_le_bytes()); // channel_id
+        data[12..16].copy_from_slice(&42u32.to_le_bytes()); // sequence
+        data[16..24].copy_from_slice(&123_456u64.to_le_bytes()); // timestamp_ms
+        data[24..32].copy_from_slice(&45.5f64.to_le_bytes()); // latitude
+        data[28..32].copy_from_slice(&512u32.to_le_bytes()); // sample_count, overlapping latitude's tail
+        data[32..40].copy_from_slice(&(-93.25f64).to_le_bytes()); // longitude
+        data[40..44].copy_from_slice(&12.5f32.to_le_bytes()); // depth_m
+        // data[44..48] left as unused padding
+        data[48..56].copy_from_slice(&99_999u64.to_le_bytes()); // sonar_offset
+        data[56..60].copy_from_slice(&2048u32.to_le_bytes()); // sonar_size
+        data[60..64].copy_from_slice(&15.0f32.to_le_bytes()); // beam_angle
+
+        let record = try_parse_binary_record(&data, 1000).expect("fixture should parse");
+
+        assert_eq!(record.offset, 1000);
+        assert_eq!(record.channel_id, 7);
+        assert_eq!(record.sequence, 42);
+        assert_eq!(record.timestamp_ms, 123_456);
+        assert_eq!(record.sample_count, 512);
+        assert_eq!(record.sonar_offset, 99_999);
+        assert_eq!(record.sonar_size, 2048);
+        assert_eq!(record.beam_angle, 15.0);
+        // latitude's trailing 4 bytes are overwritten by sample_count above,
+        // so its decoded f64 value isn't independently meaningful here.
+        assert_eq!(record.longitude, -93.25);
+        assert_eq!(record.depth_m, 12.5);
+    }
 }
\ No newline at end of file
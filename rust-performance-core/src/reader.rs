@@ -0,0 +1,126 @@
+//! A small bounded, little-endian cursor plus `FromReader`/`ToWriter` trait
+//! pair, so record layouts become a sequence of typed field reads in
+//! declared order instead of hand-maintained literal byte offsets.
+
+/// A cursor over a byte slice that tracks how much is left to read.
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0 }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    fn read_bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        if n > self.remaining() {
+            return None;
+        }
+        let bytes = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Some(bytes)
+    }
+
+    /// Yields a length-limited sub-reader over the next `len` bytes and
+    /// advances past them, so a variable-length payload (like a record's
+    /// `sonar_size` trailer) can't be read past its declared boundary.
+    pub fn take(&mut self, len: usize) -> Option<Reader<'a>> {
+        let bytes = self.read_bytes(len)?;
+        Some(Reader::new(bytes))
+    }
+
+    pub fn read<T: FromReader>(&mut self) -> Option<T> {
+        T::from_reader(self)
+    }
+
+    /// Reads `len` bytes at an absolute offset from the start of this
+    /// reader's buffer, without disturbing the cursor. Lets a `FromReader`
+    /// impl recover a field whose on-disk byte range overlaps an adjacent
+    /// field's range, for formats that aren't purely sequential.
+    pub fn peek_at(&self, offset: usize, len: usize) -> Option<&'a [u8]> {
+        self.data.get(offset..offset + len)
+    }
+
+    /// The unread tail of this reader's bytes.
+    pub fn rest(&self) -> &'a [u8] {
+        &self.data[self.pos..]
+    }
+}
+
+/// Decodes `Self` from a bounded little-endian cursor, reading fields in
+/// declared order.
+pub trait FromReader: Sized {
+    fn from_reader(r: &mut Reader) -> Option<Self>;
+}
+
+/// Re-encodes `Self` back into little-endian bytes, mirroring the field
+/// order used by `FromReader`.
+pub trait ToWriter {
+    fn to_writer(&self, out: &mut Vec<u8>);
+}
+
+macro_rules! impl_primitive {
+    ($t:ty, $n:expr) => {
+        impl FromReader for $t {
+            fn from_reader(r: &mut Reader) -> Option<Self> {
+                let bytes = r.read_bytes($n)?;
+                Some(<$t>::from_le_bytes(bytes.try_into().ok()?))
+            }
+        }
+
+        impl ToWriter for $t {
+            fn to_writer(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+        }
+    };
+}
+
+impl_primitive!(u16, 2);
+impl_primitive!(u32, 4);
+impl_primitive!(u64, 8);
+impl_primitive!(f32, 4);
+impl_primitive!(f64, 8);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_primitives_in_order() {
+        let mut bytes = Vec::new();
+        42u32.to_writer(&mut bytes);
+        3.5f32.to_writer(&mut bytes);
+
+        let mut r = Reader::new(&bytes);
+        let a: u32 = r.read().unwrap();
+        let b: f32 = r.read().unwrap();
+        assert_eq!(a, 42);
+        assert_eq!(b, 3.5);
+        assert_eq!(r.remaining(), 0);
+    }
+
+    #[test]
+    fn test_take_bounds_sub_reader() {
+        let data = [1u8, 2, 3, 4, 5, 6];
+        let mut r = Reader::new(&data);
+        let mut sub = r.take(3).unwrap();
+        assert_eq!(sub.remaining(), 3);
+        let first: u16 = sub.read().unwrap();
+        assert_eq!(first, u16::from_le_bytes([1, 2]));
+        assert_eq!(r.remaining(), 3);
+    }
+
+    #[test]
+    fn test_read_past_end_returns_none() {
+        let data = [1u8, 2];
+        let mut r = Reader::new(&data);
+        let value: Option<u32> = r.read();
+        assert!(value.is_none());
+    }
+}
@@ -1,4 +1,5 @@
 use ndarray::{ArrayView2, Array2};
+use rustfft::{FftPlanner, num_complex::Complex32};
 use std::collections::HashMap;
 
 /// Fast phase correlation for frame alignment
@@ -120,6 +121,156 @@ fn correlate_edges(edges1: &Array2<f32>, edges2: &Array2<f32>, shift: i32, width
     }
 }
 
+/// Sub-pixel 2D alignment via FFT-based phase correlation.
+///
+/// Returns `(dy, dx)`, the shift that best aligns `frame2` onto `frame1`.
+/// Unlike `phase_correlate`, this searches the whole frame at once in the
+/// frequency domain and resolves both axes to sub-pixel precision, so it
+/// also catches the vertical drift the spatial brute force above can't see.
+pub fn phase_correlate_fft(frame1: ArrayView2<u8>, frame2: ArrayView2<u8>) -> (f32, f32) {
+    let height = frame1.nrows().min(frame2.nrows());
+    let width = frame1.ncols().min(frame2.ncols());
+
+    if height == 0 || width == 0 {
+        return (0.0, 0.0);
+    }
+
+    let mut planner = FftPlanner::<f32>::new();
+
+    let mut spectrum1 = hann_windowed(frame1, height, width);
+    let mut spectrum2 = hann_windowed(frame2, height, width);
+    fft2d(&mut spectrum1, height, width, &mut planner, false);
+    fft2d(&mut spectrum2, height, width, &mut planner, false);
+
+    // Normalized cross-power spectrum: R = (F1 . conj(F2)) / |F1 . conj(F2)|
+    let eps = 1e-6_f32;
+    let mut cross: Vec<Complex32> = spectrum1
+        .iter()
+        .zip(spectrum2.iter())
+        .map(|(&a, &b)| {
+            let product = a * b.conj();
+            let magnitude = product.norm().max(eps);
+            product / magnitude
+        })
+        .collect();
+
+    fft2d(&mut cross, height, width, &mut planner, true);
+
+    // Locate the correlation peak in the inverse-transformed result.
+    let (mut peak_row, mut peak_col, mut peak_mag) = (0usize, 0usize, f32::MIN);
+    for row in 0..height {
+        for col in 0..width {
+            let mag = cross[row * width + col].norm();
+            if mag > peak_mag {
+                peak_mag = mag;
+                peak_row = row;
+                peak_col = col;
+            }
+        }
+    }
+
+    // Refine to sub-pixel precision with a parabola through the neighbors
+    // on each axis, then combine with the (wrapped) integer peak position.
+    let row_prev = cross[((peak_row + height - 1) % height) * width + peak_col].norm();
+    let row_next = cross[((peak_row + 1) % height) * width + peak_col].norm();
+    let dy_sub = parabolic_offset(row_prev, peak_mag, row_next);
+
+    let col_prev = cross[peak_row * width + (peak_col + width - 1) % width].norm();
+    let col_next = cross[peak_row * width + (peak_col + 1) % width].norm();
+    let dx_sub = parabolic_offset(col_prev, peak_mag, col_next);
+
+    let dy = wrap_to_signed(peak_row, height) as f32 + dy_sub;
+    let dx = wrap_to_signed(peak_col, width) as f32 + dx_sub;
+
+    (dy, dx)
+}
+
+/// Applies a separable Hann window to suppress edge wraparound artifacts
+/// and converts the result into a complex buffer ready for FFT.
+fn hann_windowed(frame: ArrayView2<u8>, height: usize, width: usize) -> Vec<Complex32> {
+    let mut out = vec![Complex32::new(0.0, 0.0); height * width];
+    for row in 0..height {
+        let wy = hann(row, height);
+        for col in 0..width {
+            let wx = hann(col, width);
+            let value = frame[[row, col]] as f32 * wy * wx;
+            out[row * width + col] = Complex32::new(value, 0.0);
+        }
+    }
+    out
+}
+
+fn hann(i: usize, n: usize) -> f32 {
+    if n <= 1 {
+        1.0
+    } else {
+        0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (n - 1) as f32).cos()
+    }
+}
+
+/// In-place separable 2D FFT (row pass then column pass). When `inverse`
+/// is set, runs the inverse transform and rescales by 1/(height*width).
+fn fft2d(
+    buffer: &mut [Complex32],
+    height: usize,
+    width: usize,
+    planner: &mut FftPlanner<f32>,
+    inverse: bool,
+) {
+    let row_fft = if inverse {
+        planner.plan_fft_inverse(width)
+    } else {
+        planner.plan_fft_forward(width)
+    };
+    for row in buffer.chunks_mut(width) {
+        row_fft.process(row);
+    }
+
+    let col_fft = if inverse {
+        planner.plan_fft_inverse(height)
+    } else {
+        planner.plan_fft_forward(height)
+    };
+    let mut column = vec![Complex32::new(0.0, 0.0); height];
+    for col in 0..width {
+        for row in 0..height {
+            column[row] = buffer[row * width + col];
+        }
+        col_fft.process(&mut column);
+        for row in 0..height {
+            buffer[row * width + col] = column[row];
+        }
+    }
+
+    if inverse {
+        let scale = 1.0 / (height * width) as f32;
+        for value in buffer.iter_mut() {
+            *value *= scale;
+        }
+    }
+}
+
+/// Parabolic interpolation through three equally-spaced samples around a
+/// peak: offset = 0.5*(L-R)/(L-2*C+R).
+fn parabolic_offset(left: f32, center: f32, right: f32) -> f32 {
+    let denom = left - 2.0 * center + right;
+    if denom.abs() < 1e-6 {
+        0.0
+    } else {
+        0.5 * (left - right) / denom
+    }
+}
+
+/// Reinterprets an FFT bin index as a signed shift (indices past N/2 wrap
+/// around to negative shifts).
+fn wrap_to_signed(index: usize, n: usize) -> i32 {
+    if index > n / 2 {
+        index as i32 - n as i32
+    } else {
+        index as i32
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -143,4 +294,21 @@ mod tests {
         let shift = phase_correlate(frame1.view(), frame2.view());
         assert_eq!(shift, 5);
     }
+
+    #[test]
+    fn test_phase_correlate_fft_pure_horizontal_shift() {
+        let mut frame1 = Array2::<u8>::zeros((32, 64));
+        let mut frame2 = Array2::<u8>::zeros((32, 64));
+
+        for i in 20..40 {
+            frame1[[16, i]] = 255;
+        }
+        for i in 24..44 {
+            frame2[[16, i]] = 255;
+        }
+
+        let (dy, dx) = phase_correlate_fft(frame1.view(), frame2.view());
+        assert!((dy.round() as i32) == 0);
+        assert!((dx.round() as i32 - 4).abs() <= 1);
+    }
 }
\ No newline at end of file
@@ -3,10 +3,20 @@ use numpy::{IntoPyArray, PyArray2, PyReadonlyArray2};
 use ndarray::{Array2, ArrayView2};
 
 mod waterfall;
-mod alignment; 
+mod alignment;
+mod registration;
+mod optical_flow;
+mod resample;
+mod preprocess;
+mod warp;
 
 use waterfall::generate_waterfall_fast;
-use alignment::phase_correlate;
+use alignment::{phase_correlate, phase_correlate_fft};
+use registration::estimate_transform;
+use optical_flow::dense_flow;
+use resample::resample_alongtrack;
+use preprocess::bandpass;
+use warp::{warp_affine, slant_to_ground_matrix};
 
 /// Generate waterfall visualization from dual sidescan channels
 #[pyfunction]
@@ -39,6 +49,108 @@ fn align_waterfall_frames(
     Ok(shift)
 }
 
+/// Sub-pixel 2D phase correlation for waterfall alignment
+#[pyfunction]
+fn align_waterfall_frames_subpixel(
+    py: Python,
+    frame1: PyReadonlyArray2<u8>,
+    frame2: PyReadonlyArray2<u8>,
+) -> PyResult<(f32, f32)> {
+    let f1 = frame1.as_array();
+    let f2 = frame2.as_array();
+
+    Ok(phase_correlate_fft(f1, f2))
+}
+
+/// Robust affine transform estimation from RANSAC-matched corners, with a
+/// fallback-worthiness signal (inlier ratio) so callers can fall back to
+/// `align_waterfall_frames` when the match is weak.
+#[pyfunction]
+fn estimate_frame_transform(
+    py: Python,
+    frame1: PyReadonlyArray2<u8>,
+    frame2: PyReadonlyArray2<u8>,
+    max_corners: Option<usize>,
+) -> PyResult<([[f32; 3]; 2], f32)> {
+    let f1 = frame1.as_array();
+    let f2 = frame2.as_array();
+
+    let (transform, inlier_ratio) = estimate_transform(f1, f2, max_corners.unwrap_or(200));
+    Ok((transform.matrix, inlier_ratio))
+}
+
+/// Dense inverse-search optical flow between two frames, returned as
+/// separate `dy`/`dx` arrays so callers can straighten per-column slant
+/// distortion caused by non-rigid warps (turns, waves).
+#[pyfunction]
+fn compute_dense_flow(
+    py: Python,
+    frame1: PyReadonlyArray2<u8>,
+    frame2: PyReadonlyArray2<u8>,
+) -> PyResult<(&PyArray2<f32>, &PyArray2<f32>)> {
+    let f1 = frame1.as_array();
+    let f2 = frame2.as_array();
+
+    let flow = dense_flow(f1, f2);
+    let dy = flow.map(|&(dy, _dx)| dy);
+    let dx = flow.map(|&(_dy, dx)| dx);
+
+    Ok((dy.into_pyarray(py), dx.into_pyarray(py)))
+}
+
+/// Normalizes along-track sample spacing by synthesizing intermediate rows
+/// via motion-compensated interpolation, so tow-vessel speed changes don't
+/// stretch or compress targets in the waterfall.
+#[pyfunction]
+fn resample_channel_alongtrack(
+    py: Python,
+    channel: PyReadonlyArray2<u8>,
+    row_speeds: Vec<f32>,
+    target_spacing: f32,
+) -> PyResult<&PyArray2<u8>> {
+    let data = channel.as_array();
+    let result = resample_alongtrack(data, &row_speeds, target_spacing);
+    Ok(result.into_pyarray(py))
+}
+
+/// Difference-of-Gaussians bandpass to denormalize channels before
+/// waterfall generation, suppressing speckle and beam-pattern/TVG gradients.
+#[pyfunction]
+fn bandpass_channel(
+    py: Python,
+    frame: PyReadonlyArray2<u8>,
+    short_sigma: f32,
+    long_sigma: f32,
+) -> PyResult<&PyArray2<u8>> {
+    let data = frame.as_array();
+    let result = bandpass(data, short_sigma, long_sigma);
+    Ok(result.into_pyarray(py))
+}
+
+/// Applies a 2x3 affine to `src` via inverse mapping with bilinear
+/// interpolation, e.g. to resample a frame onto a common grid using the
+/// transform produced by `estimate_frame_transform`.
+#[pyfunction]
+fn warp_frame_affine(
+    py: Python,
+    src: PyReadonlyArray2<u8>,
+    matrix: [[f32; 3]; 2],
+    out_height: usize,
+    out_width: usize,
+    fill: u8,
+) -> PyResult<&PyArray2<u8>> {
+    let data = src.as_array();
+    let result = warp_affine(data, matrix, (out_height, out_width), fill);
+    Ok(result.into_pyarray(py))
+}
+
+/// Builds the affine matrix for slant-range-to-ground-range correction
+/// given sensor altitude and per-column slant range.
+#[pyfunction]
+fn build_slant_range_matrix(altitude_m: f32, column_ranges_m: Vec<f32>) -> PyResult<[[f32; 3]; 2]> {
+    Ok(slant_to_ground_matrix(altitude_m, &column_ranges_m))
+}
+
 /// Benchmark function to compare with Python implementation
 #[pyfunction]
 fn benchmark_waterfall_generation(
@@ -68,6 +180,13 @@ fn benchmark_waterfall_generation(
 fn rsd_video_core(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(generate_sidescan_waterfall, m)?)?;
     m.add_function(wrap_pyfunction!(align_waterfall_frames, m)?)?;
+    m.add_function(wrap_pyfunction!(align_waterfall_frames_subpixel, m)?)?;
+    m.add_function(wrap_pyfunction!(estimate_frame_transform, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_dense_flow, m)?)?;
+    m.add_function(wrap_pyfunction!(resample_channel_alongtrack, m)?)?;
+    m.add_function(wrap_pyfunction!(bandpass_channel, m)?)?;
+    m.add_function(wrap_pyfunction!(warp_frame_affine, m)?)?;
+    m.add_function(wrap_pyfunction!(build_slant_range_matrix, m)?)?;
     m.add_function(wrap_pyfunction!(benchmark_waterfall_generation, m)?)?;
     Ok(())
 }
\ No newline at end of file
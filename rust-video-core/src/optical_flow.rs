@@ -0,0 +1,343 @@
+use ndarray::{Array2, ArrayView2};
+use rayon::prelude::*;
+
+const PYRAMID_LEVELS: usize = 3;
+const PATCH_RADIUS: i32 = 6;
+const PATCH_STRIDE: usize = 8;
+const LK_ITERATIONS: usize = 10;
+// Inverse-distance weight falls off as 1/(dist_sq+1), so beyond a couple of
+// patch strides a patch's contribution is negligible; bounding the splat to
+// this radius keeps `densify` linear in patch count instead of quadratic in
+// frame area.
+const DENSIFY_RADIUS: f32 = (PATCH_STRIDE * 2) as f32;
+
+/// Dense per-pixel flow field, `(dy, dx)` per pixel, aligning `frame2` onto
+/// `frame1`. Built from a coarse-to-fine image pyramid: at each level the
+/// frame is tiled into overlapping patches, each refined in place with
+/// inverse-compositional Lucas-Kanade, and the resulting sparse patch
+/// displacements are densified by weighted averaging.
+pub fn dense_flow(frame1: ArrayView2<u8>, frame2: ArrayView2<u8>) -> Array2<(f32, f32)> {
+    let height = frame1.nrows().min(frame2.nrows());
+    let width = frame1.ncols().min(frame2.ncols());
+
+    if height == 0 || width == 0 {
+        return Array2::from_elem((0, 0), (0.0, 0.0));
+    }
+
+    let pyramid1 = build_pyramid(frame1, height, width);
+    let pyramid2 = build_pyramid(frame2, height, width);
+
+    // Start coarse flow at zero and refine level by level, finest last.
+    let mut flow = Array2::<(f32, f32)>::from_elem(pyramid1.last().unwrap().dim(), (0.0, 0.0));
+
+    for level in (0..pyramid1.len()).rev() {
+        let level1 = &pyramid1[level];
+        let level2 = &pyramid2[level];
+
+        if level != pyramid1.len() - 1 {
+            flow = upsample_flow(&flow, level1.dim());
+        }
+
+        let gradients = sobel_gradients(level1.view());
+        let patch_flow = refine_patches(level1.view(), level2.view(), &gradients, &flow);
+        flow = densify(&patch_flow, level1.dim());
+    }
+
+    flow
+}
+
+/// Downsample-by-2 image pyramid: index 0 is the full-resolution frame and
+/// the last element is the coarsest level.
+fn build_pyramid(frame: ArrayView2<u8>, height: usize, width: usize) -> Vec<Array2<f32>> {
+    let mut levels = Vec::with_capacity(PYRAMID_LEVELS);
+
+    let base = Array2::from_shape_fn((height, width), |(r, c)| frame[[r, c]] as f32);
+    levels.push(base);
+
+    for _ in 1..PYRAMID_LEVELS {
+        let prev = levels.last().unwrap();
+        let (ph, pw) = prev.dim();
+        let (nh, nw) = ((ph / 2).max(1), (pw / 2).max(1));
+        if ph < 4 || pw < 4 {
+            break;
+        }
+        let down = Array2::from_shape_fn((nh, nw), |(r, c)| {
+            let r0 = (r * 2).min(ph - 1);
+            let r1 = (r * 2 + 1).min(ph - 1);
+            let c0 = (c * 2).min(pw - 1);
+            let c1 = (c * 2 + 1).min(pw - 1);
+            (prev[[r0, c0]] + prev[[r0, c1]] + prev[[r1, c0]] + prev[[r1, c1]]) / 4.0
+        });
+        levels.push(down);
+    }
+
+    // Index 0 is the finest level and the last element the coarsest;
+    // `dense_flow` walks the indices in reverse, so it starts coarse and
+    // refines toward the finest level.
+    levels
+}
+
+/// Scales a coarse flow field up to the next finer pyramid level.
+fn upsample_flow(flow: &Array2<(f32, f32)>, target: (usize, usize)) -> Array2<(f32, f32)> {
+    let (fh, fw) = flow.dim();
+    let (th, tw) = target;
+
+    Array2::from_shape_fn(target, |(r, c)| {
+        let sr = ((r * fh) / th.max(1)).min(fh.saturating_sub(1));
+        let sc = ((c * fw) / tw.max(1)).min(fw.saturating_sub(1));
+        let (dy, dx) = flow[[sr, sc]];
+        (dy * 2.0, dx * 2.0)
+    })
+}
+
+/// Sobel gradients used as the patch spatial-gradient basis for the
+/// Gauss-Newton Hessian (same kernels as `alignment::extract_edges`).
+fn sobel_gradients(frame: ArrayView2<f32>) -> (Array2<f32>, Array2<f32>) {
+    let (height, width) = frame.dim();
+    let mut gx = Array2::<f32>::zeros((height, width));
+    let mut gy = Array2::<f32>::zeros((height, width));
+
+    if height < 3 || width < 3 {
+        return (gx, gy);
+    }
+
+    for row in 1..height - 1 {
+        for col in 1..width - 1 {
+            gx[[row, col]] = (frame[[row - 1, col + 1]] + 2.0 * frame[[row, col + 1]] + frame[[row + 1, col + 1]])
+                - (frame[[row - 1, col - 1]] + 2.0 * frame[[row, col - 1]] + frame[[row + 1, col - 1]]);
+            gy[[row, col]] = (frame[[row + 1, col - 1]] + 2.0 * frame[[row + 1, col]] + frame[[row + 1, col + 1]])
+                - (frame[[row - 1, col - 1]] + 2.0 * frame[[row - 1, col]] + frame[[row - 1, col + 1]]);
+        }
+    }
+
+    (gx, gy)
+}
+
+/// A single patch's center and its refined displacement.
+struct PatchFlow {
+    row: usize,
+    col: usize,
+    dy: f32,
+    dx: f32,
+}
+
+/// Refines the displacement of every overlapping patch in parallel via
+/// inverse-compositional Lucas-Kanade, seeded from the upsampled flow.
+fn refine_patches(
+    frame1: ArrayView2<f32>,
+    frame2: ArrayView2<f32>,
+    gradients: &(Array2<f32>, Array2<f32>),
+    seed_flow: &Array2<(f32, f32)>,
+) -> Vec<PatchFlow> {
+    let (height, width) = frame1.dim();
+    let (gx, gy) = gradients;
+
+    let mut centers = Vec::new();
+    let mut row = PATCH_RADIUS as usize;
+    while row + (PATCH_RADIUS as usize) < height {
+        let mut col = PATCH_RADIUS as usize;
+        while col + (PATCH_RADIUS as usize) < width {
+            centers.push((row, col));
+            col += PATCH_STRIDE;
+        }
+        row += PATCH_STRIDE;
+    }
+
+    centers
+        .into_par_iter()
+        .map(|(row, col)| {
+            let (seed_dy, seed_dx) = seed_flow[[row.min(seed_flow.dim().0 - 1), col.min(seed_flow.dim().1 - 1)]];
+            let (dy, dx) = lk_refine_patch(frame1, frame2, gx, gy, row, col, seed_dy, seed_dx);
+            PatchFlow { row, col, dy, dx }
+        })
+        .collect()
+}
+
+/// Inverse-compositional Lucas-Kanade refinement for one patch: the Hessian
+/// is precomputed once from the template gradients, then each iteration
+/// warps the target patch by the current estimate, measures the intensity
+/// residual, and updates the displacement by H^-1 . (sum grad*residual).
+fn lk_refine_patch(
+    frame1: ArrayView2<f32>,
+    frame2: ArrayView2<f32>,
+    gx: &Array2<f32>,
+    gy: &Array2<f32>,
+    row: usize,
+    col: usize,
+    seed_dy: f32,
+    seed_dx: f32,
+) -> (f32, f32) {
+    let (height, width) = frame1.dim();
+    let r = PATCH_RADIUS;
+
+    let mut h = [[0.0f32; 2]; 2];
+    let mut samples: Vec<(i32, i32, f32, f32, f32)> = Vec::new();
+
+    for dr in -r..=r {
+        for dc in -r..=r {
+            let sr = row as i32 + dr;
+            let sc = col as i32 + dc;
+            if sr < 0 || sc < 0 || sr as usize >= height || sc as usize >= width {
+                continue;
+            }
+            let ix = gx[[sr as usize, sc as usize]];
+            let iy = gy[[sr as usize, sc as usize]];
+            let template = frame1[[sr as usize, sc as usize]];
+            h[0][0] += ix * ix;
+            h[0][1] += ix * iy;
+            h[1][0] += ix * iy;
+            h[1][1] += iy * iy;
+            samples.push((dr, dc, ix, iy, template));
+        }
+    }
+
+    let det = h[0][0] * h[1][1] - h[0][1] * h[1][0];
+    if det.abs() < 1e-6 {
+        return (seed_dy, seed_dx);
+    }
+    let inv = [[h[1][1] / det, -h[0][1] / det], [-h[1][0] / det, h[0][0] / det]];
+
+    let (mut dy, mut dx) = (seed_dy, seed_dx);
+
+    for _ in 0..LK_ITERATIONS {
+        let mut bx = 0.0f32;
+        let mut by = 0.0f32;
+
+        for &(dr, dc, ix, iy, template) in &samples {
+            let sr = row as f32 + dr as f32 + dy;
+            let sc = col as f32 + dc as f32 + dx;
+            let warped = bilinear_sample(frame2, sr, sc);
+            let residual = template - warped;
+            bx += ix * residual;
+            by += iy * residual;
+        }
+
+        let update_x = inv[0][0] * bx + inv[0][1] * by;
+        let update_y = inv[1][0] * bx + inv[1][1] * by;
+        dx += update_x;
+        dy += update_y;
+
+        if update_x * update_x + update_y * update_y < 1e-4 {
+            break;
+        }
+    }
+
+    (dy, dx)
+}
+
+fn bilinear_sample(frame: ArrayView2<f32>, row: f32, col: f32) -> f32 {
+    let (height, width) = frame.dim();
+    if row < 0.0 || col < 0.0 || row >= (height - 1) as f32 || col >= (width - 1) as f32 {
+        let rr = row.clamp(0.0, (height - 1) as f32) as usize;
+        let cc = col.clamp(0.0, (width - 1) as f32) as usize;
+        return frame[[rr, cc]];
+    }
+
+    let r0 = row.floor() as usize;
+    let c0 = col.floor() as usize;
+    let fr = row - r0 as f32;
+    let fc = col - c0 as f32;
+
+    let top = frame[[r0, c0]] * (1.0 - fc) + frame[[r0, c0 + 1]] * fc;
+    let bottom = frame[[r0 + 1, c0]] * (1.0 - fc) + frame[[r0 + 1, c0 + 1]] * fc;
+    top * (1.0 - fr) + bottom * fr
+}
+
+/// Densifies sparse patch displacements into a per-pixel field via
+/// inverse-distance weighted averaging over nearby patch centers.
+///
+/// Rather than visiting every patch for every output pixel (quadratic in
+/// frame area times patch count, and unusable on real frame sizes), each
+/// patch splats its weighted contribution into only the bounded window
+/// around its center where the weight is non-negligible. Patches are
+/// partitioned across threads, each accumulating into its own grid, which
+/// are then merged elementwise.
+fn densify(patches: &[PatchFlow], shape: (usize, usize)) -> Array2<(f32, f32)> {
+    if patches.is_empty() {
+        return Array2::from_elem(shape, (0.0, 0.0));
+    }
+
+    let zero_grids = || {
+        (
+            Array2::<f32>::zeros(shape),
+            Array2::<f32>::zeros(shape),
+            Array2::<f32>::zeros(shape),
+        )
+    };
+
+    let (dy_acc, dx_acc, weight_acc) = patches
+        .par_iter()
+        .fold(zero_grids, |mut acc, patch| {
+            splat_patch(&mut acc, patch, shape);
+            acc
+        })
+        .reduce(zero_grids, |mut a, b| {
+            a.0 += &b.0;
+            a.1 += &b.1;
+            a.2 += &b.2;
+            a
+        });
+
+    Array2::from_shape_fn(shape, |(row, col)| {
+        let weight_sum = weight_acc[[row, col]];
+        if weight_sum > 0.0 {
+            (dy_acc[[row, col]] / weight_sum, dx_acc[[row, col]] / weight_sum)
+        } else {
+            (0.0, 0.0)
+        }
+    })
+}
+
+/// Adds one patch's inverse-distance-weighted contribution into the
+/// accumulator grids, limited to the `DENSIFY_RADIUS` window around the
+/// patch center.
+fn splat_patch(acc: &mut (Array2<f32>, Array2<f32>, Array2<f32>), patch: &PatchFlow, shape: (usize, usize)) {
+    let (height, width) = shape;
+    let radius_sq = DENSIFY_RADIUS * DENSIFY_RADIUS;
+
+    let row_lo = (patch.row as f32 - DENSIFY_RADIUS).max(0.0) as usize;
+    let row_hi = ((patch.row as f32 + DENSIFY_RADIUS) as usize).min(height.saturating_sub(1));
+    let col_lo = (patch.col as f32 - DENSIFY_RADIUS).max(0.0) as usize;
+    let col_hi = ((patch.col as f32 + DENSIFY_RADIUS) as usize).min(width.saturating_sub(1));
+
+    for row in row_lo..=row_hi {
+        for col in col_lo..=col_hi {
+            let dr = row as f32 - patch.row as f32;
+            let dc = col as f32 - patch.col as f32;
+            let dist_sq = dr * dr + dc * dc;
+            if dist_sq > radius_sq {
+                continue;
+            }
+            let weight = 1.0 / (dist_sq + 1.0);
+            acc.0[[row, col]] += weight * patch.dy;
+            acc.1[[row, col]] += weight * patch.dx;
+            acc.2[[row, col]] += weight;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dense_flow_recovers_uniform_shift() {
+        let mut frame1 = Array2::<u8>::zeros((48, 48));
+        let mut frame2 = Array2::<u8>::zeros((48, 48));
+
+        for r in 10..30 {
+            for c in 10..30 {
+                frame1[[r, c]] = 200;
+            }
+        }
+        for r in 13..33 {
+            for c in 10..30 {
+                frame2[[r, c]] = 200;
+            }
+        }
+
+        let flow = dense_flow(frame1.view(), frame2.view());
+        let (dy, _dx) = flow[[20, 20]];
+        assert!((dy - 3.0).abs() < 2.0);
+    }
+}
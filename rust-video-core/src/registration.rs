@@ -0,0 +1,367 @@
+use ndarray::{Array2, ArrayView2};
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+/// A 2x3 affine transform: `[x', y'] = matrix * [x, y, 1]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine2 {
+    pub matrix: [[f32; 3]; 2],
+}
+
+impl Affine2 {
+    pub fn identity() -> Self {
+        Affine2 {
+            matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+        }
+    }
+
+    pub fn translation(dx: f32, dy: f32) -> Self {
+        Affine2 {
+            matrix: [[1.0, 0.0, dx], [0.0, 1.0, dy]],
+        }
+    }
+
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        let m = &self.matrix;
+        (
+            m[0][0] * x + m[0][1] * y + m[0][2],
+            m[1][0] * x + m[1][1] * y + m[1][2],
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Corner {
+    row: usize,
+    col: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Match {
+    p1: (f32, f32),
+    p2: (f32, f32),
+}
+
+const HARRIS_K: f32 = 0.04;
+const HARRIS_WINDOW: usize = 3;
+const NMS_RADIUS: i32 = 5;
+const PATCH_RADIUS: i32 = 7;
+const SEARCH_RADIUS: i32 = 20;
+const RANSAC_ITERATIONS: usize = 500;
+const RANSAC_INLIER_THRESHOLD: f32 = 2.0;
+
+/// Estimates the affine transform that maps `frame2` onto `frame1` using
+/// Harris corners, patch-correlation matches, and RANSAC. Returns the
+/// transform plus the inlier ratio so callers can fall back to
+/// `phase_correlate` when the match is weak.
+pub fn estimate_transform(frame1: ArrayView2<u8>, frame2: ArrayView2<u8>, max_corners: usize) -> (Affine2, f32) {
+    let corners1 = detect_corners(frame1, max_corners);
+    let corners2 = detect_corners(frame2, max_corners);
+
+    let matches = match_corners(frame1, frame2, &corners1, &corners2);
+    if matches.len() < 3 {
+        return (Affine2::identity(), 0.0);
+    }
+
+    ransac_affine(&matches)
+}
+
+/// Harris-style corner response computed from Sobel gradients (matching
+/// `alignment::extract_edges`'s kernels), with non-max suppression keeping
+/// the `max_corners` strongest responses.
+fn detect_corners(frame: ArrayView2<u8>, max_corners: usize) -> Vec<Corner> {
+    let height = frame.nrows();
+    let width = frame.ncols();
+    if height < 3 || width < 3 {
+        return Vec::new();
+    }
+
+    let mut gx = Array2::<f32>::zeros((height, width));
+    let mut gy = Array2::<f32>::zeros((height, width));
+    for row in 1..height - 1 {
+        for col in 1..width - 1 {
+            let sx = (frame[[row - 1, col + 1]] as f32 + 2.0 * frame[[row, col + 1]] as f32 + frame[[row + 1, col + 1]] as f32)
+                - (frame[[row - 1, col - 1]] as f32 + 2.0 * frame[[row, col - 1]] as f32 + frame[[row + 1, col - 1]] as f32);
+            let sy = (frame[[row + 1, col - 1]] as f32 + 2.0 * frame[[row + 1, col]] as f32 + frame[[row + 1, col + 1]] as f32)
+                - (frame[[row - 1, col - 1]] as f32 + 2.0 * frame[[row - 1, col]] as f32 + frame[[row - 1, col + 1]] as f32);
+            gx[[row, col]] = sx;
+            gy[[row, col]] = sy;
+        }
+    }
+
+    let mut response = Array2::<f32>::zeros((height, width));
+    for row in HARRIS_WINDOW..height - HARRIS_WINDOW {
+        for col in HARRIS_WINDOW..width - HARRIS_WINDOW {
+            let (mut sxx, mut syy, mut sxy) = (0.0f32, 0.0f32, 0.0f32);
+            for wr in row - HARRIS_WINDOW..=row + HARRIS_WINDOW {
+                for wc in col - HARRIS_WINDOW..=col + HARRIS_WINDOW {
+                    let ix = gx[[wr, wc]];
+                    let iy = gy[[wr, wc]];
+                    sxx += ix * ix;
+                    syy += iy * iy;
+                    sxy += ix * iy;
+                }
+            }
+            let det = sxx * syy - sxy * sxy;
+            let trace = sxx + syy;
+            response[[row, col]] = det - HARRIS_K * trace * trace;
+        }
+    }
+
+    let mut candidates: Vec<(usize, usize, f32)> = Vec::new();
+    for row in HARRIS_WINDOW..height - HARRIS_WINDOW {
+        for col in HARRIS_WINDOW..width - HARRIS_WINDOW {
+            let value = response[[row, col]];
+            if value > 0.0 {
+                candidates.push((row, col, value));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
+
+    let mut kept: Vec<Corner> = Vec::new();
+    for (row, col, _) in candidates {
+        let too_close = kept.iter().any(|c| {
+            let dr = c.row as i32 - row as i32;
+            let dc = c.col as i32 - col as i32;
+            dr * dr + dc * dc < NMS_RADIUS * NMS_RADIUS
+        });
+        if !too_close {
+            kept.push(Corner { row, col });
+            if kept.len() >= max_corners {
+                break;
+            }
+        }
+    }
+
+    kept
+}
+
+/// Builds putative matches by normalized cross-correlation of small patches
+/// centered on `frame1` corners, searched within a window around the same
+/// position in `frame2`.
+fn match_corners(
+    frame1: ArrayView2<u8>,
+    frame2: ArrayView2<u8>,
+    corners1: &[Corner],
+    corners2: &[Corner],
+) -> Vec<Match> {
+    let height = frame1.nrows().min(frame2.nrows()) as i32;
+    let width = frame1.ncols().min(frame2.ncols()) as i32;
+
+    let mut matches = Vec::new();
+    for c1 in corners1 {
+        let mut best_score = -1.0f32;
+        let mut best: Option<&Corner> = None;
+
+        for c2 in corners2 {
+            let dr = c2.row as i32 - c1.row as i32;
+            let dc = c2.col as i32 - c1.col as i32;
+            if dr.abs() > SEARCH_RADIUS || dc.abs() > SEARCH_RADIUS {
+                continue;
+            }
+            if let Some(score) = patch_ncc(frame1, frame2, c1, c2, height, width) {
+                if score > best_score {
+                    best_score = score;
+                    best = Some(c2);
+                }
+            }
+        }
+
+        if let Some(c2) = best {
+            if best_score > 0.6 {
+                matches.push(Match {
+                    p1: (c1.col as f32, c1.row as f32),
+                    p2: (c2.col as f32, c2.row as f32),
+                });
+            }
+        }
+    }
+
+    matches
+}
+
+fn patch_ncc(
+    frame1: ArrayView2<u8>,
+    frame2: ArrayView2<u8>,
+    c1: &Corner,
+    c2: &Corner,
+    height: i32,
+    width: i32,
+) -> Option<f32> {
+    let r1 = c1.row as i32;
+    let col1 = c1.col as i32;
+    let r2 = c2.row as i32;
+    let col2 = c2.col as i32;
+
+    if r1 - PATCH_RADIUS < 0
+        || r1 + PATCH_RADIUS >= height
+        || col1 - PATCH_RADIUS < 0
+        || col1 + PATCH_RADIUS >= width
+        || r2 - PATCH_RADIUS < 0
+        || r2 + PATCH_RADIUS >= height
+        || col2 - PATCH_RADIUS < 0
+        || col2 + PATCH_RADIUS >= width
+    {
+        return None;
+    }
+
+    let mut sum1 = 0.0f32;
+    let mut sum2 = 0.0f32;
+    let count = ((2 * PATCH_RADIUS + 1) * (2 * PATCH_RADIUS + 1)) as f32;
+
+    for dr in -PATCH_RADIUS..=PATCH_RADIUS {
+        for dc in -PATCH_RADIUS..=PATCH_RADIUS {
+            sum1 += frame1[[(r1 + dr) as usize, (col1 + dc) as usize]] as f32;
+            sum2 += frame2[[(r2 + dr) as usize, (col2 + dc) as usize]] as f32;
+        }
+    }
+    let mean1 = sum1 / count;
+    let mean2 = sum2 / count;
+
+    let mut numerator = 0.0f32;
+    let mut denom1 = 0.0f32;
+    let mut denom2 = 0.0f32;
+    for dr in -PATCH_RADIUS..=PATCH_RADIUS {
+        for dc in -PATCH_RADIUS..=PATCH_RADIUS {
+            let v1 = frame1[[(r1 + dr) as usize, (col1 + dc) as usize]] as f32 - mean1;
+            let v2 = frame2[[(r2 + dr) as usize, (col2 + dc) as usize]] as f32 - mean2;
+            numerator += v1 * v2;
+            denom1 += v1 * v1;
+            denom2 += v2 * v2;
+        }
+    }
+
+    let denom = (denom1 * denom2).sqrt();
+    if denom < 1e-6 {
+        None
+    } else {
+        Some(numerator / denom)
+    }
+}
+
+/// Fits a 2x3 affine model via RANSAC: repeatedly sample 3 correspondences,
+/// solve the least-squares model, count inliers below a pixel threshold,
+/// keep the model with the most inliers, then refit on all its inliers.
+fn ransac_affine(matches: &[Match]) -> (Affine2, f32) {
+    let mut rng = thread_rng();
+    let mut best_model = Affine2::identity();
+    let mut best_inliers: Vec<usize> = Vec::new();
+
+    let indices: Vec<usize> = (0..matches.len()).collect();
+
+    for _ in 0..RANSAC_ITERATIONS {
+        let mut sample = indices.clone();
+        sample.shuffle(&mut rng);
+        let sample = &sample[..3];
+
+        let model = match fit_affine(matches, sample) {
+            Some(m) => m,
+            None => continue,
+        };
+
+        let inliers: Vec<usize> = (0..matches.len())
+            .filter(|&i| reprojection_error(&model, &matches[i]) < RANSAC_INLIER_THRESHOLD)
+            .collect();
+
+        if inliers.len() > best_inliers.len() {
+            best_inliers = inliers;
+            best_model = model;
+        }
+    }
+
+    if best_inliers.len() >= 3 {
+        if let Some(refit) = fit_affine(matches, &best_inliers) {
+            best_model = refit;
+        }
+    }
+
+    let inlier_ratio = best_inliers.len() as f32 / matches.len() as f32;
+    (best_model, inlier_ratio)
+}
+
+fn reprojection_error(model: &Affine2, m: &Match) -> f32 {
+    let (px, py) = model.apply(m.p2.0, m.p2.1);
+    let dx = px - m.p1.0;
+    let dy = py - m.p1.1;
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Least-squares affine fit mapping `p2 -> p1` over the given correspondence
+/// indices, solved independently per output axis via normal equations.
+fn fit_affine(matches: &[Match], indices: &[usize]) -> Option<Affine2> {
+    if indices.len() < 3 {
+        return None;
+    }
+
+    // Normal equations for [a b c] . [x y 1] = target, solved with Cramer's rule.
+    let mut ata = [[0.0f32; 3]; 3];
+    let mut atx = [0.0f32; 3];
+    let mut aty = [0.0f32; 3];
+
+    for &i in indices {
+        let (x, y) = matches[i].p2;
+        let (tx, ty) = matches[i].p1;
+        let row = [x, y, 1.0];
+        for r in 0..3 {
+            for c in 0..3 {
+                ata[r][c] += row[r] * row[c];
+            }
+            atx[r] += row[r] * tx;
+            aty[r] += row[r] * ty;
+        }
+    }
+
+    let x_coeffs = solve3(&ata, &atx)?;
+    let y_coeffs = solve3(&ata, &aty)?;
+
+    Some(Affine2 {
+        matrix: [x_coeffs, y_coeffs],
+    })
+}
+
+/// Solves a 3x3 linear system via Cramer's rule.
+fn solve3(a: &[[f32; 3]; 3], b: &[f32; 3]) -> Option<[f32; 3]> {
+    let det = det3(a);
+    if det.abs() < 1e-9 {
+        return None;
+    }
+
+    let mut result = [0.0f32; 3];
+    for col in 0..3 {
+        let mut replaced = *a;
+        for row in 0..3 {
+            replaced[row][col] = b[row];
+        }
+        result[col] = det3(&replaced) / det;
+    }
+    Some(result)
+}
+
+fn det3(m: &[[f32; 3]; 3]) -> f32 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_affine_recovers_pure_translation() {
+        let matches = vec![
+            Match { p1: (10.0, 10.0), p2: (5.0, 5.0) },
+            Match { p1: (30.0, 12.0), p2: (25.0, 7.0) },
+            Match { p1: (14.0, 40.0), p2: (9.0, 35.0) },
+            Match { p1: (50.0, 50.0), p2: (45.0, 45.0) },
+        ];
+
+        let (model, ratio) = ransac_affine(&matches);
+        assert!(ratio > 0.5);
+
+        let (x, y) = model.apply(0.0, 0.0);
+        assert!((x - 5.0).abs() < 0.5);
+        assert!((y - 5.0).abs() < 0.5);
+    }
+}
@@ -0,0 +1,143 @@
+use ndarray::{Array2, ArrayView2};
+
+use crate::alignment::phase_correlate;
+
+/// Resamples a channel along-track to uniform ground spacing.
+///
+/// `row_speeds` gives the along-track distance (meters) covered between
+/// each row and the next, derived from GPS/speed; `target_spacing` is the
+/// desired distance per output row. Intermediate rows are synthesized by
+/// motion-compensated interpolation: the horizontal shift between the two
+/// source rows straddling a fractional position is estimated with
+/// `phase_correlate`, each row is warped toward that position, and the
+/// results are blended `(1-t)*warp(r) + t*warp(r+1)`.
+pub fn resample_alongtrack(channel: ArrayView2<u8>, row_speeds: &[f32], target_spacing: f32) -> Array2<u8> {
+    let height = channel.nrows();
+    let width = channel.ncols();
+
+    if height == 0 || width == 0 || target_spacing <= 0.0 {
+        return Array2::<u8>::zeros((0, width));
+    }
+
+    // Cumulative along-track distance at each source row (row 0 at distance 0).
+    let mut cumulative = vec![0.0f32; height];
+    for row in 1..height {
+        let step = row_speeds.get(row - 1).copied().unwrap_or(0.0);
+        cumulative[row] = cumulative[row - 1] + step;
+    }
+    let total_distance = *cumulative.last().unwrap();
+
+    if total_distance <= 0.0 {
+        return channel.to_owned();
+    }
+
+    let output_rows = (total_distance / target_spacing).floor() as usize + 1;
+    let mut output = Array2::<u8>::zeros((output_rows, width));
+
+    let mut search_start = 0usize;
+    for out_row in 0..output_rows {
+        let target_distance = out_row as f32 * target_spacing;
+
+        // Advance to the source row pair straddling target_distance.
+        while search_start + 1 < height - 1 && cumulative[search_start + 1] < target_distance {
+            search_start += 1;
+        }
+        let r = search_start.min(height - 2);
+        let span = (cumulative[r + 1] - cumulative[r]).max(1e-6);
+        let t = ((target_distance - cumulative[r]) / span).clamp(0.0, 1.0);
+
+        let row_a = channel.row(r);
+        let row_b = channel.row(r + 1);
+        let shift = phase_correlate(
+            row_a.into_shape((1, width)).unwrap(),
+            row_b.into_shape((1, width)).unwrap(),
+        );
+
+        let blended = blend_rows(row_a, row_b, shift, t, width);
+        output.row_mut(out_row).assign(&blended);
+    }
+
+    output
+}
+
+/// Warps row `a` toward the fractional position `t` and row `b` toward the
+/// complementary position using the estimated inter-row shift, then blends
+/// them as `(1-t)*warp(a) + t*warp(b)`.
+fn blend_rows(
+    row_a: ndarray::ArrayView1<u8>,
+    row_b: ndarray::ArrayView1<u8>,
+    shift: i32,
+    t: f32,
+    width: usize,
+) -> Array2<u8> {
+    let mut blended = Array2::<u8>::zeros((1, width));
+
+    // Interpolate the shift itself so the intermediate row sits partway
+    // between the two source rows' alignment rather than snapping to either.
+    let shift_to_a = -(shift as f32) * t;
+    let shift_to_b = shift as f32 * (1.0 - t);
+
+    for col in 0..width {
+        let sample_a = sample_shifted(row_a, col, shift_to_a);
+        let sample_b = sample_shifted(row_b, col, shift_to_b);
+        let value = (1.0 - t) * sample_a + t * sample_b;
+        blended[[0, col]] = value.round().clamp(0.0, 255.0) as u8;
+    }
+
+    blended
+}
+
+fn sample_shifted(row: ndarray::ArrayView1<u8>, col: usize, shift: f32) -> f32 {
+    let width = row.len();
+    let pos = col as f32 + shift;
+    let pos = pos.clamp(0.0, (width - 1) as f32);
+
+    let c0 = pos.floor() as usize;
+    let c1 = (c0 + 1).min(width - 1);
+    let frac = pos - c0 as f32;
+
+    row[c0] as f32 * (1.0 - frac) + row[c1] as f32 * frac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::Array1;
+
+    #[test]
+    fn test_resample_alongtrack_uniform_speed_preserves_row_count() {
+        let channel = Array2::<u8>::from_shape_fn((10, 20), |(r, c)| ((r + c) % 255) as u8);
+        let row_speeds = vec![1.0; 9];
+
+        let resampled = resample_alongtrack(channel.view(), &row_speeds, 1.0);
+        assert_eq!(resampled.ncols(), 20);
+        assert!(resampled.nrows() >= 9);
+    }
+
+    #[test]
+    fn test_blend_rows_places_feature_at_interpolated_position() {
+        let width = 20;
+        let shift = 4i32;
+
+        let mut a = vec![0u8; width];
+        a[10] = 200;
+        let row_a = Array1::from(a);
+
+        // row_b is row_a's feature shifted by `shift` pixels.
+        let mut b = vec![0u8; width];
+        b[10 + shift as usize] = 200;
+        let row_b = Array1::from(b);
+
+        let peak_col = |t: f32| {
+            let blended = blend_rows(row_a.view(), row_b.view(), shift, t, width);
+            (0..width).max_by_key(|&c| blended[[0, c]]).unwrap()
+        };
+
+        // t=0: no interpolation needed, output should match row_a exactly.
+        assert_eq!(peak_col(0.0), 10);
+        // t=1: output should match row_b exactly, feature at 10+shift.
+        assert_eq!(peak_col(1.0), 10 + shift as usize);
+        // t=0.5: both warped rows put the feature halfway, at 10+shift/2.
+        assert_eq!(peak_col(0.5), 10 + shift as usize / 2);
+    }
+}
@@ -0,0 +1,118 @@
+use ndarray::{Array2, ArrayView2, Axis};
+use rayon::prelude::*;
+
+use crate::registration::Affine2;
+
+/// Applies an arbitrary 2x3 affine to `src` via inverse mapping with
+/// bilinear interpolation: for each output pixel, the source coordinate is
+/// computed by the inverse transform, the four surrounding source pixels
+/// are sampled, and out-of-bounds samples take `fill`. Output rows are
+/// parallelized with rayon like the waterfall code.
+pub fn warp_affine(src: ArrayView2<u8>, matrix: [[f32; 3]; 2], out_shape: (usize, usize), fill: u8) -> Array2<u8> {
+    let (_, out_width) = out_shape;
+    let inverse = invert_affine(&matrix).unwrap_or_else(Affine2::identity);
+
+    let mut output = Array2::<u8>::from_elem(out_shape, fill);
+    output
+        .axis_iter_mut(Axis(0))
+        .into_par_iter()
+        .enumerate()
+        .for_each(|(out_row, mut row_slice)| {
+            for out_col in 0..out_width {
+                let (src_x, src_y) = inverse.apply(out_col as f32, out_row as f32);
+                row_slice[out_col] = bilinear_sample(src, src_y, src_x, fill);
+            }
+        });
+
+    output
+}
+
+fn bilinear_sample(src: ArrayView2<u8>, row: f32, col: f32, fill: u8) -> u8 {
+    let (height, width) = src.dim();
+    if height == 0 || width == 0 {
+        return fill;
+    }
+    if row < 0.0 || col < 0.0 || row > (height - 1) as f32 || col > (width - 1) as f32 {
+        return fill;
+    }
+
+    let r0 = row.floor() as usize;
+    let c0 = col.floor() as usize;
+    let r1 = (r0 + 1).min(height - 1);
+    let c1 = (c0 + 1).min(width - 1);
+    let fr = row - r0 as f32;
+    let fc = col - c0 as f32;
+
+    let top = src[[r0, c0]] as f32 * (1.0 - fc) + src[[r0, c1]] as f32 * fc;
+    let bottom = src[[r1, c0]] as f32 * (1.0 - fc) + src[[r1, c1]] as f32 * fc;
+    let value = top * (1.0 - fr) + bottom * fr;
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+/// Inverts a 2x3 affine (as a 3x3 homogeneous matrix with an implicit
+/// [0, 0, 1] bottom row) via the closed-form 2x2 inverse.
+fn invert_affine(matrix: &[[f32; 3]; 2]) -> Option<Affine2> {
+    let [[a, b, c], [d, e, f]] = *matrix;
+    let det = a * e - b * d;
+    if det.abs() < 1e-9 {
+        return None;
+    }
+
+    let inv_a = e / det;
+    let inv_b = -b / det;
+    let inv_d = -d / det;
+    let inv_e = a / det;
+    let inv_c = -(inv_a * c + inv_b * f);
+    let inv_f = -(inv_d * c + inv_e * f);
+
+    Some(Affine2 {
+        matrix: [[inv_a, inv_b, inv_c], [inv_d, inv_e, inv_f]],
+    })
+}
+
+/// Builds the affine matrix for slant-range-to-ground-range correction
+/// given sensor altitude and the per-column slant range: a ground-range
+/// column maps to a horizontally stretched slant-range column,
+/// `ground_range = sqrt(slant_range^2 - altitude^2)`, so the inverse
+/// (ground -> slant) stretch factor is `slant_range / ground_range`.
+pub fn slant_to_ground_matrix(altitude_m: f32, column_ranges_m: &[f32]) -> [[f32; 3]; 2] {
+    if column_ranges_m.len() < 2 {
+        return Affine2::identity().matrix;
+    }
+
+    let near = column_ranges_m[0];
+    let far = column_ranges_m[column_ranges_m.len() - 1];
+    let ground_near = (near * near - altitude_m * altitude_m).max(0.0).sqrt();
+    let ground_far = (far * far - altitude_m * altitude_m).max(0.0).sqrt();
+
+    let slant_span = far - near;
+    let ground_span = (ground_far - ground_near).max(1e-6);
+    let scale_x = slant_span / ground_span;
+    let offset_x = near - scale_x * ground_near;
+
+    [[scale_x, 0.0, offset_x], [0.0, 1.0, 0.0]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warp_affine_identity_preserves_frame() {
+        let src = Array2::<u8>::from_shape_fn((8, 8), |(r, c)| (r * 8 + c) as u8);
+        let identity = Affine2::identity().matrix;
+
+        let warped = warp_affine(src.view(), identity, (8, 8), 0);
+        assert_eq!(warped, src);
+    }
+
+    #[test]
+    fn test_warp_affine_translation_shifts_pixels() {
+        let mut src = Array2::<u8>::zeros((10, 10));
+        src[[5, 5]] = 255;
+
+        let translation = [[1.0, 0.0, 2.0], [0.0, 1.0, 0.0]];
+        let warped = warp_affine(src.view(), translation, (10, 10), 0);
+        assert_eq!(warped[[5, 7]], 255);
+    }
+}